@@ -1,18 +1,101 @@
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use log::warn;
 use reqwest::{blocking::Client, Url};
-use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_FIXED};
+use ring::signature::{
+    UnparsedPublicKey, ECDSA_P256_SHA256_FIXED, RSA_PKCS1_2048_8192_SHA256,
+    RSA_PSS_2048_8192_SHA256,
+};
 use serde::Deserialize;
 use serde_json::from_reader;
+use simple_asn1::{from_der, oid, ASN1Block};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
+use tuf::client::{Client as TufClient, Config as TufConfig};
+use tuf::metadata::{RawSignedMetadata, RootMetadata, TargetPath};
+use tuf::pouf::Pouf1;
+use tuf::repository::{FileSystemRepositoryBuilder, HttpRepositoryBuilder, Repository};
+use x509_cert::der::{asn1::Time, Decode, Encode};
+use x509_cert::Certificate;
 
-// See discussion in SpecificManifest::batch_signing_public_key
-const ECDSA_P256_SPKI_PREFIX: &[u8] = &[
-    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
-    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
-];
+/// Converts an X.509 `Time` (UTCTime or GeneralizedTime) into a `chrono`
+/// `DateTime<Utc>` so that certificate validity can be compared against
+/// `Utc::now()`.
+fn asn1_time_to_datetime(time: &Time) -> DateTime<Utc> {
+    let unix_duration = time.to_unix_duration();
+    Utc.timestamp(unix_duration.as_secs() as i64, unix_duration.subsec_nanos())
+}
+
+/// The algorithm identified by a PKIX SubjectPublicKeyInfo's
+/// AlgorithmIdentifier.
+#[derive(Debug, PartialEq)]
+enum SpkiAlgorithm {
+    EcdsaP256,
+    /// rsaEncryption. The SubjectPublicKeyInfo alone does not distinguish
+    /// RSASSA-PKCS1-v1_5 from RSA-PSS, since both use the same key and OID,
+    /// so callers verifying a signature against this key should try both.
+    Rsa,
+}
+
+/// Decodes a DER encoded PKIX SubjectPublicKeyInfo structure, as defined in
+/// RFC 5280 section 4.1.2.7, returning the algorithm it identifies and the
+/// raw public key bytes (i.e., the contents of the BIT STRING, with no
+/// leading unused-bits octet).
+///
+/// This walks the ASN.1 structure directly instead of relying on a hardcoded
+/// prefix, so it remains correct across re-encodings that place the same key
+/// at a different byte offset.
+fn parse_subject_public_key_info(der: &[u8]) -> Result<(SpkiAlgorithm, Vec<u8>)> {
+    let blocks = from_der(der).context("failed to parse SubjectPublicKeyInfo as DER")?;
+    let spki = match blocks.as_slice() {
+        [ASN1Block::Sequence(_, contents)] => contents,
+        _ => return Err(anyhow!("SubjectPublicKeyInfo is not a single ASN.1 SEQUENCE")),
+    };
+    let (algorithm_identifier, public_key_bits) = match spki.as_slice() {
+        [ASN1Block::Sequence(_, algorithm_identifier), ASN1Block::BitString(_, _, bits)] => {
+            (algorithm_identifier, bits)
+        }
+        _ => {
+            return Err(anyhow!(
+                "SubjectPublicKeyInfo SEQUENCE does not contain an AlgorithmIdentifier \
+                SEQUENCE followed by a BIT STRING"
+            ))
+        }
+    };
+
+    let algorithm_oid = match algorithm_identifier.first() {
+        Some(ASN1Block::ObjectIdentifier(_, oid)) => oid,
+        _ => return Err(anyhow!("AlgorithmIdentifier does not begin with an OID")),
+    };
+
+    let algorithm = if *algorithm_oid == oid!(1, 2, 840, 10045, 2, 1) {
+        // id-ecPublicKey is parameterized on the named curve OID.
+        let curve_oid = match algorithm_identifier.get(1) {
+            Some(ASN1Block::ObjectIdentifier(_, oid)) => oid,
+            _ => {
+                return Err(anyhow!(
+                    "ecPublicKey AlgorithmIdentifier is missing its named curve parameter"
+                ))
+            }
+        };
+        if *curve_oid != oid!(1, 2, 840, 10045, 3, 1, 7) {
+            return Err(anyhow!(
+                "unsupported named curve for ecPublicKey, only P-256 is supported"
+            ));
+        }
+        SpkiAlgorithm::EcdsaP256
+    } else if *algorithm_oid == oid!(1, 2, 840, 113549, 1, 1, 1) {
+        SpkiAlgorithm::Rsa
+    } else {
+        return Err(anyhow!(
+            "unsupported SubjectPublicKeyInfo algorithm identifier OID"
+        ));
+    };
+
+    Ok((algorithm, public_key_bits.clone()))
+}
 
 /// Represents the description of a batch signing public key in a specific
 /// manifest.
@@ -22,8 +105,8 @@ struct BatchSigningPublicKey {
     /// SubjectPublicKeyInfo structure of an ECDSA P256 key.
     #[serde(rename = "public-key")]
     public_key: String,
-    /// The ISO 8601 encoded UTC date at which this key expires.
-    expiration: String,
+    /// The ISO 8601 (RFC 3339) encoded UTC date at which this key expires.
+    expiration: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize, PartialEq)]
@@ -59,8 +142,44 @@ struct SpecificManifest {
     packet_encryption_certificates: HashMap<String, PacketEncryptionCertificate>,
 }
 
+/// Configuration needed to fetch and verify a specific manifest through a
+/// TUF (The Update Framework) repository instead of trusting whatever JSON a
+/// plain HTTPS GET returns.
+struct TufTrustRoot<'a> {
+    /// Path to a pinned root metadata file (`root.json`) establishing the
+    /// initial set of keys trusted to sign this TUF repository's metadata.
+    trusted_root_path: &'a Path,
+    /// Base URL from which TUF metadata (`timestamp.json`, `snapshot.json`,
+    /// `targets.json`, ...) is fetched, e.g. a CDN endpoint.
+    metadata_base_url: &'a str,
+    /// Directory used to persist the highest timestamp/snapshot/targets
+    /// versions this process has trusted for `metadata_base_url`, across
+    /// both repeated calls and process restarts. TUF's protection against
+    /// rollback to an older, still-validly-signed set of metadata depends on
+    /// comparing each fetch against what was previously trusted, so this
+    /// directory must survive at least as long as `trusted_root_path` stays
+    /// pinned to the same repository.
+    local_metadata_cache_path: &'a Path,
+}
+
 impl SpecificManifest {
-    fn from_https(base_path: &str, peer_name: &str) -> Result<SpecificManifest> {
+    /// Fetches `specific-manifest.json` for `peer_name` from `base_path` over
+    /// plain HTTPS, or, if `trust_root` is provided, through the TUF
+    /// repository it describes. The TUF path downloads the manifest as a TUF
+    /// target, so its hash and length are checked against the signed
+    /// `targets` metadata before it is ever parsed as JSON, protecting
+    /// against a compromised manifest host serving a forged set of batch
+    /// signing keys or encryption certificates, and against rollback to an
+    /// older manifest.
+    fn from_https(
+        base_path: &str,
+        peer_name: &str,
+        trust_root: Option<&TufTrustRoot>,
+    ) -> Result<SpecificManifest> {
+        if let Some(trust_root) = trust_root {
+            return SpecificManifest::from_tuf(trust_root, peer_name);
+        }
+
         let base = Url::parse(base_path).context("failed to parse base path into URL")?;
         let mut manifest_url = base
             .join(peer_name)
@@ -82,6 +201,70 @@ impl SpecificManifest {
         )
     }
 
+    /// Fetches and verifies `specific-manifest.json` for `peer_name` through
+    /// the TUF repository described by `trust_root`. See `from_https`.
+    fn from_tuf(trust_root: &TufTrustRoot, peer_name: &str) -> Result<SpecificManifest> {
+        let trusted_root_bytes = std::fs::read(trust_root.trusted_root_path)
+            .context("failed to read pinned TUF root metadata")?;
+        let raw_root = RawSignedMetadata::<Pouf1, RootMetadata>::new(trusted_root_bytes);
+
+        let metadata_base = Url::parse(trust_root.metadata_base_url)
+            .context("failed to parse TUF metadata base URL")?;
+        let remote =
+            HttpRepositoryBuilder::<Pouf1, _>::new(metadata_base, reqwest::Client::new()).build();
+
+        // Backed by a directory rather than an in-memory store, so the
+        // highest trusted timestamp/snapshot/targets versions survive across
+        // calls and process restarts, which is what makes TUF's rollback
+        // protection actually work.
+        let local = FileSystemRepositoryBuilder::<Pouf1>::new(trust_root.local_metadata_cache_path)
+            .build()
+            .context("failed to open local TUF metadata cache directory")?;
+
+        let manifest_bytes = tokio::runtime::Runtime::new()
+            .context("failed to start async runtime for TUF client")?
+            .block_on(Self::fetch_manifest_via_tuf(
+                &raw_root, local, remote, peer_name,
+            ))?;
+
+        SpecificManifest::from_reader(manifest_bytes.as_slice())
+    }
+
+    /// Bootstraps a TUF client from `raw_root`, refreshes its metadata
+    /// against `remote`, and fetches `peer_name`'s specific manifest as a
+    /// verified TUF target. Generic over both the local and remote
+    /// repositories so that tests can supply in-memory repositories instead
+    /// of touching the filesystem or talking HTTP, and so that `local` can
+    /// be reused across calls to prove that a stale, rolled-back update is
+    /// rejected once a newer one has been trusted.
+    async fn fetch_manifest_via_tuf<L: Repository<Pouf1>, R: Repository<Pouf1>>(
+        raw_root: &RawSignedMetadata<Pouf1, RootMetadata>,
+        local: L,
+        remote: R,
+        peer_name: &str,
+    ) -> Result<Vec<u8>> {
+        let mut client = TufClient::with_trusted_root(TufConfig::default(), raw_root, local, remote)
+            .await
+            .context("failed to bootstrap TUF client from pinned root metadata")?;
+
+        client
+            .update()
+            .await
+            .context("failed to refresh TUF timestamp/snapshot/targets metadata")?;
+
+        let manifest_path = format!("{}/specific-manifest.json", peer_name);
+        let target_path =
+            TargetPath::new(manifest_path).context("failed to construct TUF target path")?;
+
+        let mut manifest_bytes = Vec::new();
+        client
+            .fetch_target_to_stream(&target_path, &mut manifest_bytes)
+            .await
+            .context("failed to fetch and verify specific manifest via TUF")?;
+
+        Ok(manifest_bytes)
+    }
+
     fn from_file(path: &Path) -> Result<SpecificManifest> {
         SpecificManifest::from_reader(File::open(path).context("failed to open manifest file")?)
     }
@@ -95,41 +278,197 @@ impl SpecificManifest {
         Ok(manifest)
     }
 
-    /// Returns the ECDSA P256 public key corresponding to the provided key
-    /// identifier, if it exists in the manifest.
-    fn batch_signing_public_key(&self, identifier: &str) -> Result<UnparsedPublicKey<Vec<u8>>> {
-        // No Rust crate that we have found gives us an easy way to parse PKIX
-        // SubjectPublicKeyInfo structures to get at the public key which can
-        // then be used in ring::signature. Since we know the keys we deal with
-        // should always be ECDSA P256, we can instead check that the binary
-        // blob inside the PEM has the expected prefix for this kind of key in
-        // this kind of encoding, as suggested in this GitHub issue on ring:
-        // https://github.com/briansmith/ring/issues/881
-        let key = self
-            .batch_signing_public_keys
+    /// Decodes every entry in `batch_signing_public_keys` into a `Keyring`,
+    /// centralizing selection of the `ring` verification algorithm per key
+    /// instead of assuming every key in the manifest is ECDSA P256. Entries
+    /// that fail to decode are skipped rather than failing the whole
+    /// manifest; see `Keyring::new`.
+    fn keyring(&self) -> Keyring {
+        Keyring::new(&self.batch_signing_public_keys)
+    }
+
+    /// Returns the public key embedded in the X.509 packet encryption
+    /// certificate corresponding to the provided identifier, if it exists in
+    /// the manifest, is currently within its validity window, and contains a
+    /// P256 key.
+    fn packet_encryption_key(&self, identifier: &str) -> Result<UnparsedPublicKey<Vec<u8>>> {
+        let cert_entry = self
+            .packet_encryption_certificates
             .get(identifier)
-            .context(format!("no value for key {}", identifier))?;
+            .context(format!("no value for certificate {}", identifier))?;
 
-        let pem = pem::parse(&key.public_key)
-            .context(format!("failed to parse key entry {} as PEM", identifier))?;
-        if pem.tag != "PUBLIC KEY" {
+        let pem = pem::parse(&cert_entry.certificate).context(format!(
+            "failed to parse certificate entry {} as PEM",
+            identifier
+        ))?;
+        if pem.tag != "CERTIFICATE" {
             return Err(anyhow!(
-                "key for identifier {} is not a PEM encoded public key"
+                "certificate for identifier {} is not a PEM encoded certificate",
+                identifier
             ));
         }
-        if pem.contents.len() < ECDSA_P256_SPKI_PREFIX.len() {
-            return Err(anyhow!("PEM contents not long enough to contain ASN.1 encoded ECDSA P256 SubjectPublicKeyInfo"));
+
+        let certificate = Certificate::from_der(&pem.contents).context(format!(
+            "failed to parse certificate {} as an X.509 certificate",
+            identifier
+        ))?;
+
+        let validity = &certificate.tbs_certificate.validity;
+        let not_before = asn1_time_to_datetime(&validity.not_before);
+        let not_after = asn1_time_to_datetime(&validity.not_after);
+        let now = Utc::now();
+        if now < not_before || now > not_after {
+            return Err(anyhow!(
+                "certificate {} is outside its validity window ({} to {})",
+                identifier,
+                not_before,
+                not_after
+            ));
         }
-        if &pem.contents[..ECDSA_P256_SPKI_PREFIX.len()] != ECDSA_P256_SPKI_PREFIX {
+
+        let spki_der = certificate
+            .tbs_certificate
+            .subject_public_key_info
+            .to_der()
+            .context(format!(
+                "failed to re-encode SubjectPublicKeyInfo from certificate {}",
+                identifier
+            ))?;
+        let (algorithm, public_key) = parse_subject_public_key_info(&spki_der).context(format!(
+            "failed to parse SubjectPublicKeyInfo from certificate {}",
+            identifier
+        ))?;
+
+        match algorithm {
+            SpkiAlgorithm::EcdsaP256 => {
+                Ok(UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, public_key))
+            }
+            SpkiAlgorithm::Rsa => Err(anyhow!(
+                "certificate {} contains an RSA key, only P256 packet encryption keys are supported",
+                identifier
+            )),
+        }
+    }
+}
+
+/// A set of batch signing public keys decoded from a manifest and keyed by
+/// identifier, each paired with its expiration. Centralizing the decode here
+/// means the `ring` verification algorithm for each key is selected once,
+/// from its parsed SPKI algorithm identifier, rather than being assumed
+/// every time a key is looked up.
+struct Keyring {
+    // Each key maps to every `ring` verification algorithm it could
+    // plausibly have been used with. This is almost always a single
+    // algorithm, except RSA keys: a SubjectPublicKeyInfo alone can't tell
+    // RSASSA-PKCS1-v1_5 and RSA-PSS apart, so both candidates are kept and
+    // tried in turn at verification time.
+    keys: HashMap<String, (Vec<UnparsedPublicKey<Vec<u8>>>, DateTime<Utc>)>,
+}
+
+impl Keyring {
+    /// Decodes every key in `batch_signing_public_keys`. An entry that fails
+    /// to decode (malformed PEM, truncated SPKI, an algorithm we don't
+    /// support yet, ...) is skipped with a warning rather than failing the
+    /// whole keyring, so that one bad or not-yet-supported key during
+    /// rotation doesn't zero out verification for every other key in the
+    /// manifest.
+    fn new(batch_signing_public_keys: &HashMap<String, BatchSigningPublicKey>) -> Keyring {
+        let mut keys = HashMap::new();
+        for (identifier, key) in batch_signing_public_keys {
+            match Keyring::decode_key(key) {
+                Ok(candidate_keys) => {
+                    keys.insert(identifier.clone(), (candidate_keys, key.expiration));
+                }
+                Err(err) => {
+                    warn!("skipping undecodable batch signing key {}: {:#}", identifier, err);
+                }
+            }
+        }
+
+        Keyring { keys }
+    }
+
+    /// Decodes a single batch signing key into the `ring` verification
+    /// algorithm(s) it could plausibly have been used with.
+    fn decode_key(key: &BatchSigningPublicKey) -> Result<Vec<UnparsedPublicKey<Vec<u8>>>> {
+        let pem = pem::parse(&key.public_key).context("failed to parse key entry as PEM")?;
+        if pem.tag != "PUBLIC KEY" {
+            return Err(anyhow!("key entry is not a PEM encoded public key"));
+        }
+
+        let (algorithm, public_key) =
+            parse_subject_public_key_info(&pem.contents).context("failed to parse SubjectPublicKeyInfo")?;
+
+        Ok(match algorithm {
+            SpkiAlgorithm::EcdsaP256 => {
+                vec![UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, public_key)]
+            }
+            SpkiAlgorithm::Rsa => vec![
+                UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, public_key.clone()),
+                UnparsedPublicKey::new(&RSA_PSS_2048_8192_SHA256, public_key),
+            ],
+        })
+    }
+
+    /// Returns the identifiers of keys in this keyring whose expiration has
+    /// not yet passed.
+    fn active_key_identifiers(&self) -> Vec<&str> {
+        let now = Utc::now();
+        self.keys
+            .iter()
+            .filter(|(_, (_, expiration))| *expiration > now)
+            .map(|(identifier, _)| identifier.as_str())
+            .collect()
+    }
+
+    /// Verifies `signature` over `content` against the key identified by
+    /// `identifier`, failing if there is no such key, it has expired, or
+    /// none of its candidate algorithms verify the signature.
+    fn verify_with(&self, identifier: &str, content: &[u8], signature: &[u8]) -> Result<()> {
+        let (candidate_keys, expiration) = self
+            .keys
+            .get(identifier)
+            .context(format!("no value for key {}", identifier))?;
+
+        if *expiration <= Utc::now() {
             return Err(anyhow!(
-                "PEM contents are not ASN.1 encoded ECDSA P256 SubjectPublicKeyInfo"
+                "key for identifier {} expired at {}",
+                identifier,
+                expiration
             ));
         }
 
-        Ok(UnparsedPublicKey::new(
-            &ECDSA_P256_SHA256_FIXED,
-            Vec::from(&pem.contents[ECDSA_P256_SPKI_PREFIX.len()..]),
-        ))
+        if candidate_keys
+            .iter()
+            .any(|key| key.verify(content, signature).is_ok())
+        {
+            Ok(())
+        } else {
+            Err(anyhow!("signature verification failed for key {}", identifier))
+        }
+    }
+
+    /// Verifies `signature` over `content` against every unexpired key in
+    /// this keyring, succeeding if any one of them verifies. Useful when the
+    /// key that produced a signature isn't known ahead of time, e.g. during
+    /// key rotation.
+    fn verify(&self, content: &[u8], signature: &[u8]) -> Result<()> {
+        let now = Utc::now();
+        let verifies = self
+            .keys
+            .values()
+            .filter(|(_, expiration)| *expiration > now)
+            .any(|(candidate_keys, _)| {
+                candidate_keys
+                    .iter()
+                    .any(|key| key.verify(content, signature).is_ok())
+            });
+
+        if verifies {
+            Ok(())
+        } else {
+            Err(anyhow!("no unexpired key in keyring verified the signature"))
+        }
     }
 }
 
@@ -141,6 +480,10 @@ mod tests {
     };
     use ring::rand::SystemRandom;
     use std::io::Cursor;
+    use tuf::crypto::{Ed25519PrivateKey, PrivateKey};
+    use tuf::metadata::MetadataPath;
+    use tuf::repo_builder::RepoBuilder;
+    use tuf::repository::EphemeralRepository;
 
     #[test]
     fn load_manifest() {
@@ -155,7 +498,7 @@ mod tests {
     }},
     "batch-signing-public-keys": {{
         "fake-key-2": {{
-        "expiration": "",
+        "expiration": "2099-01-01T00:00:00Z",
         "public-key": "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----"
       }}
     }},
@@ -171,7 +514,7 @@ mod tests {
         expected_batch_keys.insert(
             "fake-key-2".to_owned(),
             BatchSigningPublicKey {
-                expiration: "".to_string(),
+                expiration: "2099-01-01T00:00:00Z".parse().unwrap(),
                 public_key: format!(
                     "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----",
                     DEFAULT_INGESTOR_SUBJECT_PUBLIC_KEY_INFO
@@ -193,14 +536,15 @@ mod tests {
             peer_validation_bucket: "us-west-1/validation".to_string(),
         };
         assert_eq!(manifest, expected_manifest);
-        let batch_signing_key = manifest.batch_signing_public_key("fake-key-2").unwrap();
+        let keyring = manifest.keyring();
         let content = b"some content";
         let signature = default_ingestor_private_key()
             .sign(&SystemRandom::new(), content)
             .unwrap();
-        batch_signing_key
-            .verify(content, signature.as_ref())
+        keyring
+            .verify_with("fake-key-2", content, signature.as_ref())
             .unwrap();
+        keyring.verify(content, signature.as_ref()).unwrap();
     }
 
     #[test]
@@ -218,7 +562,7 @@ mod tests {
     },
     "batch-signing-public-keys": {
         "fake-key-2": {
-        "expiration": "",
+        "expiration": "2099-01-01T00:00:00Z",
         "public-key": "-----BEGIN PUBLIC KEY-----\nfoo\n-----END PUBLIC KEY-----"
       }
     },
@@ -237,7 +581,7 @@ mod tests {
     },
     "batch-signing-public-keys": {
         "fake-key-2": {
-        "expiration": "",
+        "expiration": "2099-01-01T00:00:00Z",
         "public-key": "-----BEGIN PUBLIC KEY-----\nfoo\n-----END PUBLIC KEY-----"
       }
     },
@@ -256,7 +600,7 @@ mod tests {
     },
     "batch-signing-public-keys": {
         "fake-key-2": {
-        "expiration": "",
+        "expiration": "2099-01-01T00:00:00Z",
         "public-key": "-----BEGIN PUBLIC KEY-----\nfoo\n-----END PUBLIC KEY-----"
       }
     },
@@ -286,7 +630,7 @@ mod tests {
     },
     "batch-signing-public-keys": {
         "fake-key-2": {
-        "expiration": "",
+        "expiration": "2099-01-01T00:00:00Z",
         "public-key": "-----BEGIN EC PUBLIC KEY-----\nMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEIKh3MccE1cdSF4pnEb+U0MmGYfkoQzOl2aiaJ6D9ZudqDdGiyA9YSUq3yia56nYJh5mk+HlzTX+AufoNR2bfrg==\n-----END EC PUBLIC KEY-----"
       }
     },
@@ -305,7 +649,7 @@ mod tests {
     },
     "batch-signing-public-keys": {
         "fake-key-2": {
-        "expiration": "",
+        "expiration": "2099-01-01T00:00:00Z",
         "public-key": "-----BEGIN PUBLIC KEY-----\nBIl6j+J6dYttxALdjISDv6ZI4/VWVEhUzaS05LgrsfswmbLOgNt9HUC2E0w+9RqZx3XMkdEHBHfNuCSMpOwofVSq3TfyKwn0NrftKisKKVSaTOt5seJ67P5QL4hxgPWvxw==\n-----END PUBLIC KEY-----"
       }
     },
@@ -324,7 +668,7 @@ mod tests {
     },
     "batch-signing-public-keys": {
         "fake-key-2": {
-        "expiration": "",
+        "expiration": "2099-01-01T00:00:00Z",
         "public-key": "-----BEGIN PUBLIC KEY-----\ndG9vIHNob3J0Cg==\n-----END PUBLIC KEY-----"
       }
     },
@@ -336,7 +680,337 @@ mod tests {
         for invalid_manifest in &manifests_with_invalid_public_keys {
             let reader = Cursor::new(invalid_manifest);
             let manifest = SpecificManifest::from_reader(reader).unwrap();
-            assert!(manifest.batch_signing_public_key("fake-key-1").is_err());
+            // The undecodable "fake-key-2" entry is skipped rather than
+            // failing the whole keyring.
+            assert!(manifest.keyring().active_key_identifiers().is_empty());
         }
     }
+
+    #[test]
+    fn expired_batch_signing_key() {
+        let reader = Cursor::new(format!(
+            r#"
+{{
+    "format": 0,
+    "packet-encryption-certificates": {{
+        "fake-key-1": {{
+            "certificate": "who cares"
+        }}
+    }},
+    "batch-signing-public-keys": {{
+        "future-key": {{
+        "expiration": "2099-01-01T00:00:00Z",
+        "public-key": "-----BEGIN PUBLIC KEY-----\n{0}\n-----END PUBLIC KEY-----"
+      }},
+        "expired-key": {{
+        "expiration": "2020-01-01T00:00:00Z",
+        "public-key": "-----BEGIN PUBLIC KEY-----\n{0}\n-----END PUBLIC KEY-----"
+      }}
+    }},
+    "ingestion-bucket": "us-west-1/ingestion",
+    "peer-validation-bucket": "us-west-1/validation"
+}}
+    "#,
+            DEFAULT_INGESTOR_SUBJECT_PUBLIC_KEY_INFO
+        ));
+        let manifest = SpecificManifest::from_reader(reader).unwrap();
+        let keyring = manifest.keyring();
+
+        let content = b"some content";
+        let signature = default_ingestor_private_key()
+            .sign(&SystemRandom::new(), content)
+            .unwrap();
+
+        keyring
+            .verify_with("future-key", content, signature.as_ref())
+            .unwrap();
+        keyring
+            .verify_with("expired-key", content, signature.as_ref())
+            .unwrap_err();
+        keyring.verify(content, signature.as_ref()).unwrap();
+
+        let mut active_keys = keyring.active_key_identifiers();
+        active_keys.sort_unstable();
+        assert_eq!(active_keys, vec!["future-key"]);
+    }
+
+    #[test]
+    fn packet_encryption_certificate() {
+        // A self-signed P256 certificate valid from 2020 to 2099.
+        const VALID_CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----\nMIIBHzCBxqADAgECAhQQdywYeItBxCtsL83j/4+wgDbEXjAKBggqhkjOPQQDAjAP\nMQ0wCwYDVQQDDAR0ZXN0MCAXDTIwMDEwMTAwMDAwMFoYDzIwOTkwMTAxMDAwMDAw\nWjAPMQ0wCwYDVQQDDAR0ZXN0MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE/HwQ\nIM2blQPH6LdGbooRFOkYNebIAEx/6VSyftad88wZyrvzGQ6wSvnlfs0EHBZvkzMG\nb6wuepDi7lqG86jsGTAKBggqhkjOPQQDAgNIADBFAiEA+9q2QZ/blRqN58OS5qaH\nzymoiOSeEa7SARuBdNAZEs8CICoah54rhGdHf6l0MmuY7XA07VDOOG0fayd0HB+/\nHDB5\n-----END CERTIFICATE-----";
+        // A self-signed P256 certificate valid from 2010 to 2011 only.
+        const EXPIRED_CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----\nMIIBHjCBxKADAgECAhRRMcZ1Aho9ohejEstOhYywaZHg6TAKBggqhkjOPQQDAjAP\nMQ0wCwYDVQQDDAR0ZXN0MB4XDTEwMDEwMTAwMDAwMFoXDTExMDEwMTAwMDAwMFow\nDzENMAsGA1UEAwwEdGVzdDBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABIdk/FIj\nC13KqDcIXX42PBzfUBIWBIT6Tx+cxEToPW4Pd/VGeAYvv5Sqq10nrmga6QU4jULA\nFF77WbnPl+vW/wQwCgYIKoZIzj0EAwIDSQAwRgIhAJ8kkwNHGdRv9jjk7Bsjyc3d\nWhyOS406C0A/05diBSwFAiEA5GhCNqdVnTVNPIu4L3tYW1TLRO5gRWCOvLDnJyMQ\n7GU=\n-----END CERTIFICATE-----";
+
+        let reader = Cursor::new(format!(
+            r#"
+{{
+    "format": 0,
+    "packet-encryption-certificates": {{
+        "valid-cert": {{
+            "certificate": "{}"
+        }},
+        "expired-cert": {{
+            "certificate": "{}"
+        }}
+    }},
+    "batch-signing-public-keys": {{
+        "fake-key-2": {{
+        "expiration": "2099-01-01T00:00:00Z",
+        "public-key": "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----"
+      }}
+    }},
+    "ingestion-bucket": "us-west-1/ingestion",
+    "peer-validation-bucket": "us-west-1/validation"
+}}
+    "#,
+            VALID_CERTIFICATE, EXPIRED_CERTIFICATE, DEFAULT_INGESTOR_SUBJECT_PUBLIC_KEY_INFO
+        ));
+        let manifest = SpecificManifest::from_reader(reader).unwrap();
+
+        manifest.packet_encryption_key("valid-cert").unwrap();
+        manifest.packet_encryption_key("expired-cert").unwrap_err();
+        manifest.packet_encryption_key("no-such-cert").unwrap_err();
+    }
+
+    #[test]
+    fn rsa_batch_signing_key() {
+        // SubjectPublicKeyInfo for a 2048 bit RSA key.
+        const RSA_SUBJECT_PUBLIC_KEY_INFO: &str = "MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEA1DuLIZ2+0ap/ih2i7FzSMrDmxvHB7T0L0b7Z05re7LrQHGkhW9kDU3lgGKD1BkPo1qtEE2axmH69PNsT8F/LyOw4rtnD3Slp5OHlT8igFPmgpVPeYLWqSI2Flm/g4+BNSJbOOU7C7C7WOX14k16hkrbpHtGoCTdWT8E68rb7ZM2on4ulnevJVV/kdEZtlQ7g56+pIi10dNFkNtlhFwX2FysFrB2Fq8+PXnq8zIJhjca+/xZgwcb57aIUfbxDeTMB1SRyDNdDkS4vlmMxkR0xyYPZQNJHDS27PFmM0BbDmEJS3vX1iOaN4viw5l2nTV82Rv2GvI+Kzm+grGgJnEyVJwIDAQAB";
+        // PKCS#1 v1.5 SHA-256 signature over b"some content" made with the
+        // corresponding private key.
+        const PKCS1_SIGNATURE: &str = "E+hV4wyJUO0tur5sKCLBLLwmGqxP0honWyY/wd2xtmW2vgOs5NHAXFtI146iIatzCxyav20dQyayTX2wBzZOioXo2xnnDGw4/TExx4dNpSctCfDVtlRgU7aB6D+iQDmG6C9kOqrQseV07oIIMS1CHBiBFMOeEHpWeFmOd+eZjmMa0nV+CyRW0J3zN4SXs890ZDOo6GXRYYO9YvZ69R4COfMQWYn/kVN9DSJ2grwZJnkPRx8co6yqU2EF5xbgHfsPT/A2rPycE2Yc5ynuf9W3N2jzwTDYN0P295P7n+KQoBnwcu0kp88/LOYVUup/1JwL/gDKv+i2Q7b1o8krzomJPg==";
+        // RSA-PSS SHA-256 signature over the same content and key, with the
+        // salt length fixed to 32 bytes (the digest length), which is what
+        // ring::signature::RSA_PSS_2048_8192_SHA256 requires.
+        const PSS_SIGNATURE: &str = "It0+XnEUWJdQCQUExI3cz17xqmDVfQ+mz3JwOZID32+U7wBcTtyds/2VjrDGRXvqJ6nvZPOtEptDEiTA793uZHE4rG02ZDPqhQsvf/ezbBYBpjofMxq0JYyt74yubgaZDF4/4vLEVBkMDrMUEnlTEpPQpCD16hupBS74z3fl5KHKiyu8QW/f4GoCZ6YDTzv+4a3nAjvFsWzrBD4j5TscbWax81hftg+laFgJmHjQ/TC3cVB9rEK/mvFuETgeLw0Pk/91Pjhay/ey0iUZQzUBytcHbS6sDGQdOSe+Xk6M2fFsD9YvrAzp+2sbzZmHHOVHT+4en9dARi1ZJejs3kjsLA==";
+
+        let reader = Cursor::new(format!(
+            r#"
+{{
+    "format": 0,
+    "packet-encryption-certificates": {{
+        "fake-key-1": {{
+            "certificate": "who cares"
+        }}
+    }},
+    "batch-signing-public-keys": {{
+        "rsa-key": {{
+        "expiration": "2099-01-01T00:00:00Z",
+        "public-key": "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----"
+      }}
+    }},
+    "ingestion-bucket": "us-west-1/ingestion",
+    "peer-validation-bucket": "us-west-1/validation"
+}}
+    "#,
+            RSA_SUBJECT_PUBLIC_KEY_INFO
+        ));
+        let manifest = SpecificManifest::from_reader(reader).unwrap();
+        let keyring = manifest.keyring();
+
+        let content = b"some content";
+        let pkcs1_signature = base64::decode(PKCS1_SIGNATURE).unwrap();
+        let pss_signature = base64::decode(PSS_SIGNATURE).unwrap();
+
+        keyring
+            .verify_with("rsa-key", content, &pkcs1_signature)
+            .unwrap();
+        keyring
+            .verify_with("rsa-key", content, &pss_signature)
+            .unwrap();
+        keyring
+            .verify_with("rsa-key", b"wrong content", &pkcs1_signature)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn fetch_manifest_via_tuf() {
+        use tuf::metadata::MetadataVersion;
+
+        let manifest_json_v1 = br#"{
+    "format": 0,
+    "ingestion-bucket": "us-west-1/ingestion",
+    "peer-validation-bucket": "us-west-1/validation",
+    "batch-signing-public-keys": {},
+    "packet-encryption-certificates": {}
+}"#
+        .to_vec();
+        let manifest_json_v2 = br#"{
+    "format": 0,
+    "ingestion-bucket": "us-west-1/ingestion-v2",
+    "peer-validation-bucket": "us-west-1/validation",
+    "batch-signing-public-keys": {},
+    "packet-encryption-certificates": {}
+}"#
+        .to_vec();
+
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            // One key plays all four TUF roles here, which is good enough
+            // for exercising the fetch/verify path against an in-memory
+            // repository; a real deployment would use distinct keys per
+            // role.
+            let key = PrivateKey::Ed25519(Ed25519PrivateKey::from_pkcs8(
+                &Ed25519PrivateKey::pkcs8().unwrap(),
+            ));
+            let remote = EphemeralRepository::<Pouf1>::new();
+
+            RepoBuilder::create(&remote)
+                .trusted_root_keys(&[&key])
+                .trusted_targets_keys(&[&key])
+                .trusted_snapshot_keys(&[&key])
+                .trusted_timestamp_keys(&[&key])
+                .add_target("test-peer/specific-manifest.json", manifest_json_v1.clone())
+                .await
+                .unwrap()
+                .commit()
+                .await
+                .unwrap();
+
+            // Read back the root metadata we just wrote, the same way a
+            // deployment would ship it as the pinned `root.json`.
+            let mut raw_root_bytes = Vec::new();
+            remote
+                .fetch_metadata(
+                    &MetadataPath::root(),
+                    &MetadataVersion::Number(1),
+                    None,
+                    &mut raw_root_bytes,
+                )
+                .await
+                .unwrap();
+            let raw_root = RawSignedMetadata::<Pouf1, RootMetadata>::new(raw_root_bytes.clone());
+
+            // Stash a copy of the version-1 timestamp/snapshot/targets
+            // metadata and target content now, before it's superseded below,
+            // so it can be replayed later as a rollback attempt.
+            let mut timestamp_v1 = Vec::new();
+            remote
+                .fetch_metadata(
+                    &MetadataPath::timestamp(),
+                    &MetadataVersion::None,
+                    None,
+                    &mut timestamp_v1,
+                )
+                .await
+                .unwrap();
+            let mut snapshot_v1 = Vec::new();
+            remote
+                .fetch_metadata(
+                    &MetadataPath::snapshot(),
+                    &MetadataVersion::Number(1),
+                    None,
+                    &mut snapshot_v1,
+                )
+                .await
+                .unwrap();
+            let mut targets_v1 = Vec::new();
+            remote
+                .fetch_metadata(
+                    &MetadataPath::targets(),
+                    &MetadataVersion::Number(1),
+                    None,
+                    &mut targets_v1,
+                )
+                .await
+                .unwrap();
+
+            // `local` represents the trusted-metadata cache that `from_tuf`
+            // persists on disk via `FileSystemRepository` in production.
+            // Cloning an `EphemeralRepository` shares its backing store
+            // rather than copying it, so reusing the clones below across
+            // multiple `fetch_manifest_via_tuf` calls models a single
+            // long-lived local cache, the same way the on-disk one is reused
+            // across repeated calls and process restarts.
+            let local = EphemeralRepository::<Pouf1>::new();
+
+            let fetched = SpecificManifest::fetch_manifest_via_tuf(
+                &raw_root,
+                local.clone(),
+                remote.clone(),
+                "test-peer",
+            )
+            .await
+            .unwrap();
+            assert_eq!(fetched, manifest_json_v1);
+
+            // Advance the repository to version 2 with new manifest content,
+            // and confirm the same persistent local cache picks up the
+            // newer, validly-signed version.
+            RepoBuilder::create(&remote)
+                .trusted_root_keys(&[&key])
+                .trusted_targets_keys(&[&key])
+                .trusted_snapshot_keys(&[&key])
+                .trusted_timestamp_keys(&[&key])
+                .add_target("test-peer/specific-manifest.json", manifest_json_v2.clone())
+                .await
+                .unwrap()
+                .commit()
+                .await
+                .unwrap();
+
+            let fetched = SpecificManifest::fetch_manifest_via_tuf(
+                &raw_root,
+                local.clone(),
+                remote.clone(),
+                "test-peer",
+            )
+            .await
+            .unwrap();
+            assert_eq!(fetched, manifest_json_v2);
+
+            // Now simulate a compromised or MITM'd metadata host replaying
+            // the old, still-validly-signed version-1 timestamp/snapshot/
+            // targets/target bytes captured above. The local cache has
+            // already trusted version 2, so this rollback must be rejected.
+            let rollback_remote = EphemeralRepository::<Pouf1>::new();
+            rollback_remote
+                .store_metadata(
+                    &MetadataPath::root(),
+                    &MetadataVersion::Number(1),
+                    &mut raw_root_bytes.as_slice(),
+                )
+                .await
+                .unwrap();
+            rollback_remote
+                .store_metadata(
+                    &MetadataPath::timestamp(),
+                    &MetadataVersion::None,
+                    &mut timestamp_v1.as_slice(),
+                )
+                .await
+                .unwrap();
+            rollback_remote
+                .store_metadata(
+                    &MetadataPath::snapshot(),
+                    &MetadataVersion::Number(1),
+                    &mut snapshot_v1.as_slice(),
+                )
+                .await
+                .unwrap();
+            rollback_remote
+                .store_metadata(
+                    &MetadataPath::targets(),
+                    &MetadataVersion::Number(1),
+                    &mut targets_v1.as_slice(),
+                )
+                .await
+                .unwrap();
+            rollback_remote
+                .store_target(
+                    &TargetPath::new("test-peer/specific-manifest.json").unwrap(),
+                    &mut manifest_json_v1.as_slice(),
+                )
+                .await
+                .unwrap();
+
+            SpecificManifest::fetch_manifest_via_tuf(
+                &raw_root,
+                local.clone(),
+                rollback_remote,
+                "test-peer",
+            )
+            .await
+            .unwrap_err();
+        });
+    }
 }